@@ -0,0 +1,118 @@
+use serde::Serialize;
+
+use crate::WeeklyActivity;
+
+/// Everything an achievement rule needs to decide whether it's earned.
+pub struct StatsContext {
+    pub overall_streak: usize,
+    pub yearly_streak: usize,
+    pub monthly_streak: usize,
+    pub total_points: usize,
+    pub total_sessions: usize,
+    pub weekly_trend: Vec<WeeklyActivity>,
+}
+
+/// One achievement rule: an id/name pair plus the predicate (and progress
+/// estimate) that decide whether a `StatsContext` has earned it.
+pub struct Achievement {
+    pub id: String,
+    pub name: String,
+    evaluate: Box<dyn Fn(&StatsContext) -> bool + Send + Sync>,
+    progress: Box<dyn Fn(&StatsContext) -> f64 + Send + Sync>,
+}
+
+impl Achievement {
+    fn new(
+        id: impl Into<String>,
+        name: impl Into<String>,
+        evaluate: impl Fn(&StatsContext) -> bool + Send + Sync + 'static,
+        progress: impl Fn(&StatsContext) -> f64 + Send + Sync + 'static,
+    ) -> Self {
+        Self {
+            id: id.into(),
+            name: name.into(),
+            evaluate: Box::new(evaluate),
+            progress: Box::new(progress),
+        }
+    }
+}
+
+/// Earned/unearned status for one achievement, with progress toward earning it.
+#[derive(Serialize)]
+pub struct AchievementStatus {
+    pub id: String,
+    pub name: String,
+    pub earned: bool,
+    /// `1.0` once earned, otherwise how close `ctx` is to earning it, in `[0.0, 1.0]`.
+    pub progress: f64,
+}
+
+fn streak_milestone(days: usize) -> Achievement {
+    Achievement::new(
+        format!("streak-{days}"),
+        format!("{days}-day streak"),
+        move |ctx| ctx.overall_streak >= days,
+        move |ctx| (ctx.overall_streak as f64 / days as f64).min(1.0),
+    )
+}
+
+fn point_tier(points: usize, name: &str) -> Achievement {
+    Achievement::new(
+        format!("points-{points}"),
+        name,
+        move |ctx| ctx.total_points >= points,
+        move |ctx| (ctx.total_points as f64 / points as f64).min(1.0),
+    )
+}
+
+fn best_week_bonus(points: usize, name: &str) -> Achievement {
+    Achievement::new(
+        format!("best-week-{points}"),
+        name,
+        move |ctx| ctx.weekly_trend.iter().any(|week| week.points >= points),
+        move |ctx| {
+            let best = ctx.weekly_trend.iter().map(|week| week.points).max().unwrap_or(0);
+            (best as f64 / points as f64).min(1.0)
+        },
+    )
+}
+
+/// The full catalog of achievement rules, evaluated in one pass over a `StatsContext`.
+fn registry() -> Vec<Achievement> {
+    vec![
+        streak_milestone(7),
+        streak_milestone(30),
+        streak_milestone(100),
+        point_tier(100, "Century Club"),
+        point_tier(500, "Half-Grand"),
+        point_tier(1000, "Grindstone"),
+        best_week_bonus(50, "Heavy Week"),
+    ]
+}
+
+/// Evaluate every achievement rule against `ctx`.
+pub fn evaluate_all(ctx: &StatsContext) -> Vec<AchievementStatus> {
+    registry()
+        .into_iter()
+        .map(|achievement| {
+            let earned = (achievement.evaluate)(ctx);
+            let progress = if earned { 1.0 } else { (achievement.progress)(ctx) };
+            AchievementStatus {
+                id: achievement.id,
+                name: achievement.name,
+                earned,
+                progress,
+            }
+        })
+        .collect()
+}
+
+/// The subset of `evaluate_all` that's actually earned, as plain names —
+/// used where only a flat achievement list is needed (e.g. `StatisticsResponse`).
+pub fn earned_names(ctx: &StatsContext) -> Vec<String> {
+    evaluate_all(ctx)
+        .into_iter()
+        .filter(|status| status.earned)
+        .map(|status| status.name)
+        .collect()
+}
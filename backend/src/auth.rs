@@ -0,0 +1,185 @@
+use std::future::Future;
+use std::pin::Pin;
+use std::sync::Arc;
+
+use actix_session::{Session, SessionExt};
+use actix_web::dev::Payload;
+use actix_web::{web, FromRequest, HttpRequest, HttpResponse, Responder};
+use bcrypt::{hash, verify, DEFAULT_COST};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+use serde::{Deserialize, Serialize};
+
+use crate::db::Database;
+use crate::ApiError;
+
+const TOKEN_LENGTH: usize = 32;
+const USERNAME_MIN_LEN: usize = 3;
+const USERNAME_MAX_LEN: usize = 32;
+
+/// The authenticated user for the current request, resolved from a `Bearer` token
+/// or, failing that, the signed session cookie set by `/api/login`.
+pub struct AuthUser(pub i64);
+
+impl AuthUser {
+    pub fn user_id(&self) -> i64 {
+        self.0
+    }
+}
+
+impl FromRequest for AuthUser {
+    type Error = actix_web::Error;
+    type Future = Pin<Box<dyn Future<Output = Result<Self, Self::Error>>>>;
+
+    fn from_request(req: &HttpRequest, _payload: &mut Payload) -> Self::Future {
+        let db = req.app_data::<web::Data<Arc<dyn Database>>>().cloned();
+        let token = extract_bearer_token(req);
+        let session_user_id = req.get_session().get::<i64>("user_id").ok().flatten();
+
+        Box::pin(async move {
+            if let Some(token) = token {
+                let db = db.ok_or_else(|| {
+                    actix_web::error::ErrorInternalServerError("Database not configured")
+                })?;
+                let user = db
+                    .find_user_by_token(&token)
+                    .await
+                    .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+                    .ok_or_else(|| actix_web::error::ErrorUnauthorized("Invalid API token"))?;
+
+                return Ok(AuthUser(user.id));
+            }
+
+            if let Some(user_id) = session_user_id {
+                return Ok(AuthUser(user_id));
+            }
+
+            Err(actix_web::error::ErrorUnauthorized(
+                "Missing API token or session",
+            ))
+        })
+    }
+}
+
+fn extract_bearer_token(req: &HttpRequest) -> Option<String> {
+    req.headers()
+        .get(actix_web::http::header::AUTHORIZATION)
+        .and_then(|value| value.to_str().ok())
+        .and_then(|value| value.strip_prefix("Bearer "))
+        .map(str::to_string)
+}
+
+fn generate_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// `^[A-Za-z0-9_]{3,32}$`, checked by hand rather than pulling in a regex engine
+/// for a single fixed pattern.
+fn validate_username(username: &str) -> bool {
+    (USERNAME_MIN_LEN..=USERNAME_MAX_LEN).contains(&username.len())
+        && username
+            .chars()
+            .all(|c| c.is_ascii_alphanumeric() || c == '_')
+}
+
+#[derive(Deserialize, Default)]
+pub struct RegisterRequest {
+    /// Minutes east of UTC, e.g. `-300` for US Eastern. Defaults to UTC (`0`).
+    tz_offset_minutes: Option<i32>,
+    /// Optional username/password pair to attach alongside the API token, enabling
+    /// cookie-based login via `/api/login`. Both must be present to set either.
+    username: Option<String>,
+    password: Option<String>,
+}
+
+#[derive(Serialize)]
+pub struct RegisterResponse {
+    token: String,
+}
+
+/// `POST /api/register` — create a new account and hand back its API token.
+/// Optionally attaches a username/password login in the same request.
+pub async fn register(
+    db: web::Data<Arc<dyn Database>>,
+    body: Option<web::Json<RegisterRequest>>,
+) -> Result<impl Responder, ApiError> {
+    let body = body.map(web::Json::into_inner).unwrap_or_default();
+
+    // Validate the username/password invariant before touching the database, so a bad
+    // request never leaves behind an orphaned token-only account.
+    let credentials = match (body.username, body.password) {
+        (Some(username), Some(password)) => {
+            if !validate_username(&username) {
+                return Err(ApiError::InvalidInput(
+                    "Username must be 3-32 characters of letters, digits, or underscores".into(),
+                ));
+            }
+            Some((username, password))
+        }
+        (None, None) => None,
+        _ => {
+            return Err(ApiError::InvalidInput(
+                "Username and password must both be present to attach a login".into(),
+            ))
+        }
+    };
+
+    let token = generate_token();
+    let user = db
+        .create_user(&token, body.tz_offset_minutes.unwrap_or(0))
+        .await?;
+
+    if let Some((username, password)) = credentials {
+        let password_hash = hash(&password, DEFAULT_COST)
+            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+        db.set_credentials(user.id, &username, &password_hash)
+            .await?;
+    }
+
+    Ok(HttpResponse::Created().json(RegisterResponse { token: user.token }))
+}
+
+#[derive(Deserialize)]
+pub struct LoginRequest {
+    username: String,
+    password: String,
+}
+
+#[derive(Serialize)]
+pub struct LoginResponse {
+    token: String,
+}
+
+/// `POST /api/login` — verify a username/password pair and start a cookie session,
+/// so subsequent requests can authenticate without resending the API token.
+pub async fn login(
+    db: web::Data<Arc<dyn Database>>,
+    credentials: web::Json<LoginRequest>,
+    session: Session,
+) -> Result<impl Responder, ApiError> {
+    let user = db
+        .find_user_by_username(&credentials.username)
+        .await?
+        .ok_or_else(|| ApiError::InvalidInput("Invalid username or password".into()))?;
+
+    let password_hash = user
+        .password_hash
+        .as_deref()
+        .ok_or_else(|| ApiError::InvalidInput("Invalid username or password".into()))?;
+
+    let valid = verify(&credentials.password, password_hash)
+        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    if !valid {
+        return Err(ApiError::InvalidInput("Invalid username or password".into()));
+    }
+
+    session
+        .insert("user_id", user.id)
+        .map_err(|e| ApiError::SerializationError(e.to_string()))?;
+
+    Ok(HttpResponse::Ok().json(LoginResponse { token: user.token }))
+}
@@ -0,0 +1,219 @@
+use std::io::Write;
+
+use actix_web::body::{to_bytes, BoxBody};
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::header::{ContentEncoding, ACCEPT_ENCODING, CONTENT_ENCODING, CONTENT_TYPE};
+use actix_web::Error;
+use brotli::CompressorWriter;
+use flate2::write::GzEncoder;
+use flate2::Compression;
+use futures::future::{ready, LocalBoxFuture, Ready};
+
+/// Response compression negotiated against `Accept-Encoding`, supporting gzip and brotli.
+#[derive(Clone)]
+pub struct CompressionConfig {
+    /// Bodies smaller than this are left uncompressed — gzip/brotli framing overhead
+    /// outweighs the saving on a handful of bytes.
+    pub min_size_bytes: usize,
+}
+
+impl Default for CompressionConfig {
+    fn default() -> Self {
+        Self {
+            min_size_bytes: 256,
+        }
+    }
+}
+
+/// Content types that are already compressed (images, archives) or otherwise not
+/// worth re-encoding — e.g. session attachments. Re-compressing these wastes CPU,
+/// forces the whole body into memory instead of streaming it, and can even grow
+/// the payload.
+fn is_incompressible_content_type(content_type: &str) -> bool {
+    let media_type = content_type.split(';').next().unwrap_or("").trim();
+    matches!(
+        media_type,
+        "image/png"
+            | "image/jpeg"
+            | "image/gif"
+            | "image/webp"
+            | "image/avif"
+            | "video/mp4"
+            | "video/webm"
+            | "audio/mpeg"
+            | "audio/ogg"
+            | "application/zip"
+            | "application/gzip"
+            | "application/x-gzip"
+            | "application/octet-stream"
+            | "application/pdf"
+    )
+}
+
+/// Buffers each response whose content type is compressible, compresses it with gzip
+/// or brotli per `CompressionConfig`, and leaves small bodies untouched.
+pub struct ResponseCompression {
+    config: CompressionConfig,
+}
+
+impl ResponseCompression {
+    pub fn new(config: CompressionConfig) -> Self {
+        Self { config }
+    }
+}
+
+impl<S> Transform<S, ServiceRequest> for ResponseCompression
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<BoxBody>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Transform = ResponseCompressionMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(ResponseCompressionMiddleware {
+            service,
+            config: self.config.clone(),
+        }))
+    }
+}
+
+pub struct ResponseCompressionMiddleware<S> {
+    service: S,
+    config: CompressionConfig,
+}
+
+impl<S> Service<ServiceRequest> for ResponseCompressionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<BoxBody>, Error = Error> + 'static,
+    S::Future: 'static,
+{
+    type Response = ServiceResponse<BoxBody>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let config = self.config.clone();
+        let accept_encoding = req
+            .headers()
+            .get(ACCEPT_ENCODING)
+            .and_then(|value| value.to_str().ok())
+            .map(str::to_string);
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let res = fut.await?;
+
+            let content_type = res
+                .response()
+                .headers()
+                .get(CONTENT_TYPE)
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_ascii_lowercase);
+            let skip_compression = content_type
+                .as_deref()
+                .map(is_incompressible_content_type)
+                .unwrap_or(false);
+
+            let (req, res) = res.into_parts();
+            let (mut http_res, body) = res.into_parts();
+
+            if skip_compression {
+                return Ok(ServiceResponse::new(req, http_res.set_body(body)));
+            }
+
+            let bytes = to_bytes(body)
+                .await
+                .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+
+            if bytes.len() < config.min_size_bytes {
+                return Ok(ServiceResponse::new(
+                    req,
+                    http_res.set_body(BoxBody::new(bytes)),
+                ));
+            }
+
+            let encoding = negotiate_encoding(accept_encoding.as_deref());
+            let compressed = match encoding {
+                ContentEncoding::Br => compress_brotli(&bytes),
+                ContentEncoding::Gzip => compress_gzip(&bytes)?,
+                _ => {
+                    return Ok(ServiceResponse::new(
+                        req,
+                        http_res.set_body(BoxBody::new(bytes)),
+                    ))
+                }
+            };
+
+            http_res.headers_mut().insert(
+                CONTENT_ENCODING,
+                actix_web::http::header::HeaderValue::from_static(match encoding {
+                    ContentEncoding::Br => "br",
+                    ContentEncoding::Gzip => "gzip",
+                    _ => "identity",
+                }),
+            );
+
+            Ok(ServiceResponse::new(
+                req,
+                http_res.set_body(BoxBody::new(compressed)),
+            ))
+        })
+    }
+}
+
+/// Picks the highest-`q` coding the client actually listed, honoring `;q=0` as a
+/// rejection. A missing `Accept-Encoding` header means "send it uncompressed" —
+/// we never hand a client a gzip/brotli body it didn't ask for.
+fn negotiate_encoding(accept_encoding: Option<&str>) -> ContentEncoding {
+    let Some(value) = accept_encoding else {
+        return ContentEncoding::Identity;
+    };
+
+    let mut best: Option<(&str, f32)> = None;
+    for offer in value.split(',') {
+        let mut parts = offer.split(';').map(str::trim);
+        let coding = match parts.next() {
+            Some(coding) if !coding.is_empty() => coding,
+            _ => continue,
+        };
+        let q: f32 = parts
+            .find_map(|param| param.strip_prefix("q="))
+            .and_then(|q| q.parse().ok())
+            .unwrap_or(1.0);
+
+        if q > 0.0 && best.map(|(_, best_q)| q > best_q).unwrap_or(true) {
+            best = Some((coding, q));
+        }
+    }
+
+    match best.map(|(coding, _)| coding) {
+        Some(coding) if coding.eq_ignore_ascii_case("br") => ContentEncoding::Br,
+        Some(coding) if coding.eq_ignore_ascii_case("gzip") => ContentEncoding::Gzip,
+        _ => ContentEncoding::Identity,
+    }
+}
+
+fn compress_gzip(bytes: &[u8]) -> Result<Vec<u8>, Error> {
+    let mut encoder = GzEncoder::new(Vec::new(), Compression::default());
+    encoder
+        .write_all(bytes)
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
+    encoder
+        .finish()
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))
+}
+
+fn compress_brotli(bytes: &[u8]) -> Vec<u8> {
+    let mut output = Vec::new();
+    {
+        let mut writer = CompressorWriter::new(&mut output, 4096, 5, 22);
+        let _ = writer.write_all(bytes);
+    }
+    output
+}
@@ -0,0 +1,147 @@
+use std::rc::Rc;
+
+use actix_web::body::MessageBody;
+use actix_web::cookie::Cookie;
+use actix_web::dev::{forward_ready, Service, ServiceRequest, ServiceResponse, Transform};
+use actix_web::http::Method;
+use actix_web::Error;
+use futures::future::{ready, LocalBoxFuture, Ready};
+use rand::distributions::Alphanumeric;
+use rand::Rng;
+
+const TOKEN_LENGTH: usize = 32;
+
+/// Which cookie/header carry the double-submit CSRF token, which methods require it,
+/// and which paths are exempt (auth bootstrap routes a fresh client can't have a
+/// token for yet).
+#[derive(Clone)]
+pub struct CsrfConfig {
+    pub cookie_name: String,
+    pub header_name: String,
+    pub protected_methods: Vec<Method>,
+    pub exempt_paths: Vec<String>,
+}
+
+impl Default for CsrfConfig {
+    /// `csrf_token` cookie, `X-CSRF-Token` header, guarding POST/PUT/DELETE except the
+    /// account-bootstrap routes, which a client has no prior session or cookie for.
+    fn default() -> Self {
+        Self {
+            cookie_name: "csrf_token".to_string(),
+            header_name: "X-CSRF-Token".to_string(),
+            protected_methods: vec![Method::POST, Method::PUT, Method::DELETE],
+            exempt_paths: vec!["/api/register".to_string(), "/api/login".to_string()],
+        }
+    }
+}
+
+fn generate_csrf_token() -> String {
+    rand::thread_rng()
+        .sample_iter(&Alphanumeric)
+        .take(TOKEN_LENGTH)
+        .map(char::from)
+        .collect()
+}
+
+/// Double-submit-cookie CSRF guard. Safe requests (and `config.exempt_paths`) get handed
+/// a token cookie if they don't already have one; requests using a protected method must
+/// echo that token back in `config.header_name`, or they're rejected with 403 before the
+/// handler runs.
+///
+/// Only the header is checked, not a form field — so protected requests with a form
+/// body, such as multipart attachment uploads, must also carry the token in the header.
+pub struct CsrfProtection {
+    config: Rc<CsrfConfig>,
+}
+
+impl CsrfProtection {
+    pub fn new(config: CsrfConfig) -> Self {
+        Self {
+            config: Rc::new(config),
+        }
+    }
+}
+
+impl<S, B> Transform<S, ServiceRequest> for CsrfProtection
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Transform = CsrfProtectionMiddleware<S>;
+    type InitError = ();
+    type Future = Ready<Result<Self::Transform, Self::InitError>>;
+
+    fn new_transform(&self, service: S) -> Self::Future {
+        ready(Ok(CsrfProtectionMiddleware {
+            service,
+            config: self.config.clone(),
+        }))
+    }
+}
+
+pub struct CsrfProtectionMiddleware<S> {
+    service: S,
+    config: Rc<CsrfConfig>,
+}
+
+impl<S, B> Service<ServiceRequest> for CsrfProtectionMiddleware<S>
+where
+    S: Service<ServiceRequest, Response = ServiceResponse<B>, Error = Error> + 'static,
+    S::Future: 'static,
+    B: MessageBody + 'static,
+{
+    type Response = ServiceResponse<B>;
+    type Error = Error;
+    type Future = LocalBoxFuture<'static, Result<Self::Response, Self::Error>>;
+
+    forward_ready!(service);
+
+    fn call(&self, req: ServiceRequest) -> Self::Future {
+        let config = self.config.clone();
+        let is_exempt = config
+            .exempt_paths
+            .iter()
+            .any(|path| path == req.path());
+        let is_protected = !is_exempt && config.protected_methods.contains(req.method());
+        let has_cookie = req.cookie(&config.cookie_name).is_some();
+
+        if is_protected {
+            let cookie_value = req
+                .cookie(&config.cookie_name)
+                .map(|cookie| cookie.value().to_string());
+            let header_value = req
+                .headers()
+                .get(config.header_name.as_str())
+                .and_then(|value| value.to_str().ok())
+                .map(str::to_string);
+
+            let tokens_match = matches!(
+                (&cookie_value, &header_value),
+                (Some(cookie), Some(header)) if cookie == header
+            );
+            if !tokens_match {
+                return Box::pin(async move {
+                    Err(actix_web::error::ErrorForbidden("CSRF token mismatch"))
+                });
+            }
+        }
+
+        let fut = self.service.call(req);
+        Box::pin(async move {
+            let mut res = fut.await?;
+
+            if !is_protected && !has_cookie {
+                let cookie = Cookie::build(config.cookie_name.clone(), generate_csrf_token())
+                    .http_only(false)
+                    .path("/")
+                    .finish();
+                let _ = res.response_mut().add_cookie(&cookie);
+            }
+
+            Ok(res)
+        })
+    }
+}
@@ -0,0 +1,175 @@
+mod sqlite;
+#[cfg(feature = "postgres")]
+mod postgres;
+
+pub use sqlite::{SqliteDb, SqliteSettings};
+#[cfg(feature = "postgres")]
+pub use postgres::{PostgresDb, PostgresSettings};
+
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use serde::{Deserialize, Serialize};
+use sqlx::FromRow;
+use std::fmt::{Display, Formatter};
+use std::future::Future;
+use std::time::Instant;
+
+use crate::{ApiError, Session, SessionLog};
+
+/// Run `query`, logging its elapsed time under `label` so operators can spot slow
+/// queries as the session table grows without hand-adding timing to every call site.
+pub(crate) async fn timed<T, E>(
+    label: &'static str,
+    query: impl Future<Output = Result<T, E>>,
+) -> Result<T, E> {
+    let start = Instant::now();
+    let result = query.await;
+    let elapsed_ms = start.elapsed().as_millis();
+    match &result {
+        Ok(_) => tracing::debug!(query = label, elapsed_ms, "db query ok"),
+        Err(_) => tracing::warn!(query = label, elapsed_ms, "db query failed"),
+    }
+    result
+}
+
+/// A registered account, identified by the API token handed out at registration.
+///
+/// `username`/`password_hash` are only set once a password login has been attached
+/// via `set_credentials` — token-only accounts leave both `None`.
+#[derive(Serialize, Deserialize, Clone, FromRow)]
+pub struct User {
+    pub id: i64,
+    pub token: String,
+    /// Minutes east of UTC, used to localize streak calculations to the user's own calendar day.
+    pub tz_offset_minutes: i32,
+    pub username: Option<String>,
+    #[serde(skip_serializing)]
+    pub password_hash: Option<String>,
+}
+
+/// A proof attachment (photo, screenshot, exported log, ...) uploaded against a session.
+///
+/// `data` is excluded from JSON serialization — metadata responses list attachments,
+/// but the blob itself is only ever served through the dedicated download endpoint.
+#[derive(Serialize, Deserialize, Clone, FromRow)]
+pub struct Attachment {
+    pub id: i64,
+    pub session_id: i64,
+    pub filename: String,
+    pub content_type: String,
+    pub byte_length: i64,
+    pub uploaded_at: String,
+    #[serde(skip_serializing)]
+    pub data: Vec<u8>,
+}
+
+pub type DbResult<T> = std::result::Result<T, DbError>;
+
+/// Errors surfaced by a `Database` implementation, independent of the engine behind it.
+#[derive(Debug)]
+pub enum DbError {
+    Connection(String),
+    Query(String),
+}
+
+impl Display for DbError {
+    fn fmt(&self, f: &mut Formatter<'_>) -> std::fmt::Result {
+        match self {
+            DbError::Connection(message) => write!(f, "connection error: {}", message),
+            DbError::Query(message) => write!(f, "query error: {}", message),
+        }
+    }
+}
+
+impl std::error::Error for DbError {}
+
+impl From<DbError> for ApiError {
+    fn from(err: DbError) -> Self {
+        ApiError::DatabaseError(err.to_string())
+    }
+}
+
+/// Backend-agnostic persistence for grind sessions.
+///
+/// Handlers depend on `web::Data<Arc<dyn Database>>` rather than a concrete pool,
+/// so a Postgres implementation (or a mock used in tests) can be dropped in
+/// without touching the HTTP layer.
+#[async_trait]
+pub trait Database: Send + Sync {
+    /// Run any setup needed before the database can be used (schema creation, migrations, ...).
+    async fn init(&self) -> DbResult<()>;
+
+    /// Register a new user under the given API token, localized to `tz_offset_minutes`
+    /// (minutes east of UTC) for streak calculations.
+    async fn create_user(&self, token: &str, tz_offset_minutes: i32) -> DbResult<User>;
+
+    /// Resolve an API token to its owning user, if any.
+    async fn find_user_by_token(&self, token: &str) -> DbResult<Option<User>>;
+
+    /// Look up a user by id, e.g. to read their `tz_offset_minutes` back out.
+    async fn find_user(&self, user_id: i64) -> DbResult<Option<User>>;
+
+    /// Resolve a username to its owning user, if any, for password login.
+    async fn find_user_by_username(&self, username: &str) -> DbResult<Option<User>>;
+
+    /// Attach a username/password login to an already-registered account.
+    async fn set_credentials(
+        &self,
+        user_id: i64,
+        username: &str,
+        password_hash: &str,
+    ) -> DbResult<()>;
+
+    /// Persist a new session log entry owned by `user_id`, stamped with the UTC
+    /// instant `logged_at`, and return the stored `Session`.
+    async fn insert_session(
+        &self,
+        user_id: i64,
+        log: &SessionLog,
+        logged_at: NaiveDateTime,
+    ) -> DbResult<Session>;
+
+    /// Fetch every session owned by `user_id`, ordered by date.
+    async fn all_sessions(&self, user_id: i64) -> DbResult<Vec<Session>>;
+
+    /// Look up a single session by id, e.g. to confirm ownership before an attachment upload.
+    async fn find_session(&self, session_id: i64) -> DbResult<Option<Session>>;
+
+    /// Fetch every registered user, e.g. for background jobs that fan out per account.
+    async fn all_users(&self) -> DbResult<Vec<User>>;
+
+    /// Read back a piece of persisted state for a named background job.
+    async fn get_job_state(&self, job: &str) -> DbResult<Option<String>>;
+
+    /// Persist state for a named background job, overwriting any previous value.
+    async fn set_job_state(&self, job: &str, value: &str) -> DbResult<()>;
+
+    /// Add `session_id` to the posting list of each of `terms`, for full-text search.
+    async fn index_session_terms(&self, session_id: i64, terms: &[String]) -> DbResult<()>;
+
+    /// Ids of `user_id`'s sessions whose posting list contains an exact match for any
+    /// of `terms` — a single batched `IN (...)` query rather than one round-trip per
+    /// term, since callers pass a whole fuzzy-match variant set at once.
+    async fn session_ids_for_terms(&self, user_id: i64, terms: &[String]) -> DbResult<Vec<i64>>;
+
+    /// Ids of `user_id`'s sessions with a term starting with `prefix`, for matching an
+    /// in-progress final query word.
+    async fn session_ids_for_prefix(&self, user_id: i64, prefix: &str) -> DbResult<Vec<i64>>;
+
+    /// Store a proof attachment's blob and metadata against `session_id`.
+    async fn insert_attachment(
+        &self,
+        session_id: i64,
+        filename: &str,
+        content_type: &str,
+        data: &[u8],
+        uploaded_at: &str,
+    ) -> DbResult<Attachment>;
+
+    /// Look up one attachment by id, scoped to the session it was uploaded against.
+    async fn find_attachment(
+        &self,
+        session_id: i64,
+        attachment_id: i64,
+    ) -> DbResult<Option<Attachment>>;
+}
@@ -0,0 +1,430 @@
+use async_trait::async_trait;
+use chrono::NaiveDateTime;
+use sqlx::SqlitePool;
+
+use super::{timed, Attachment, Database, DbError, DbResult, User};
+use crate::{Session, SessionLog};
+
+/// Connection settings for the SQLite backend.
+pub struct SqliteSettings {
+    pub database_url: String,
+}
+
+impl Default for SqliteSettings {
+    fn default() -> Self {
+        Self {
+            database_url: "sqlite::memory:".to_string(),
+        }
+    }
+}
+
+/// `Database` backed by a `sqlx::SqlitePool`.
+pub struct SqliteDb {
+    pool: SqlitePool,
+}
+
+impl SqliteDb {
+    pub async fn connect(settings: SqliteSettings) -> DbResult<Self> {
+        let pool = sqlx::sqlite::SqlitePoolOptions::new()
+            .max_connections(5)
+            .connect(&settings.database_url)
+            .await
+            .map_err(|e| DbError::Connection(e.to_string()))?;
+
+        Ok(Self { pool })
+    }
+}
+
+#[async_trait]
+impl Database for SqliteDb {
+    async fn init(&self) -> DbResult<()> {
+        timed(
+            "sqlite:init:users_table",
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS users (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    token TEXT NOT NULL UNIQUE,
+                    tz_offset_minutes INTEGER NOT NULL DEFAULT 0,
+                    username TEXT UNIQUE,
+                    password_hash TEXT
+                );
+                "#,
+            )
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        timed(
+            "sqlite:init:session_table",
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS session (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    user_id INTEGER NOT NULL REFERENCES users(id),
+                    date TEXT NOT NULL,
+                    session_type TEXT NOT NULL,
+                    logged_at TEXT NOT NULL
+                );
+                "#,
+            )
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        timed(
+            "sqlite:init:job_state_table",
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS job_state (
+                    job TEXT PRIMARY KEY,
+                    value TEXT NOT NULL
+                );
+                "#,
+            )
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        timed(
+            "sqlite:init:search_index_table",
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS search_index (
+                    term TEXT NOT NULL,
+                    session_id INTEGER NOT NULL REFERENCES session(id)
+                );
+                "#,
+            )
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        timed(
+            "sqlite:init:search_index_term_idx",
+            sqlx::query("CREATE INDEX IF NOT EXISTS search_index_term ON search_index(term);")
+                .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        timed(
+            "sqlite:init:attachment_table",
+            sqlx::query(
+                r#"
+                CREATE TABLE IF NOT EXISTS attachment (
+                    id INTEGER PRIMARY KEY AUTOINCREMENT,
+                    session_id INTEGER NOT NULL REFERENCES session(id),
+                    filename TEXT NOT NULL,
+                    content_type TEXT NOT NULL,
+                    byte_length INTEGER NOT NULL,
+                    uploaded_at TEXT NOT NULL,
+                    data BLOB NOT NULL
+                );
+                "#,
+            )
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn create_user(&self, token: &str, tz_offset_minutes: i32) -> DbResult<User> {
+        let id = timed(
+            "sqlite:create_user",
+            sqlx::query("INSERT INTO users (token, tz_offset_minutes) VALUES (?, ?)")
+                .bind(token)
+                .bind(tz_offset_minutes)
+                .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?
+        .last_insert_rowid();
+
+        Ok(User {
+            id,
+            token: token.to_string(),
+            tz_offset_minutes,
+            username: None,
+            password_hash: None,
+        })
+    }
+
+    async fn find_user_by_token(&self, token: &str) -> DbResult<Option<User>> {
+        timed(
+            "sqlite:find_user_by_token",
+            sqlx::query_as::<_, User>(
+                "SELECT id, token, tz_offset_minutes, username, password_hash FROM users WHERE token = ?",
+            )
+            .bind(token)
+            .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn find_user(&self, user_id: i64) -> DbResult<Option<User>> {
+        timed(
+            "sqlite:find_user",
+            sqlx::query_as::<_, User>(
+                "SELECT id, token, tz_offset_minutes, username, password_hash FROM users WHERE id = ?",
+            )
+            .bind(user_id)
+            .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn find_user_by_username(&self, username: &str) -> DbResult<Option<User>> {
+        timed(
+            "sqlite:find_user_by_username",
+            sqlx::query_as::<_, User>(
+                "SELECT id, token, tz_offset_minutes, username, password_hash FROM users WHERE username = ?",
+            )
+            .bind(username)
+            .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn set_credentials(
+        &self,
+        user_id: i64,
+        username: &str,
+        password_hash: &str,
+    ) -> DbResult<()> {
+        timed(
+            "sqlite:set_credentials",
+            sqlx::query("UPDATE users SET username = ?, password_hash = ? WHERE id = ?")
+                .bind(username)
+                .bind(password_hash)
+                .bind(user_id)
+                .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn insert_session(
+        &self,
+        user_id: i64,
+        log: &SessionLog,
+        logged_at: NaiveDateTime,
+    ) -> DbResult<Session> {
+        let logged_at = logged_at.format("%Y-%m-%d %H:%M:%S").to_string();
+        let id = timed(
+            "sqlite:insert_session",
+            sqlx::query(
+                "INSERT INTO session (user_id, date, session_type, logged_at) VALUES (?, ?, ?, ?)",
+            )
+            .bind(user_id)
+            .bind(&log.date)
+            .bind(&log.session_type)
+            .bind(&logged_at)
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?
+        .last_insert_rowid();
+
+        Ok(Session {
+            id,
+            user_id,
+            date: log.date.clone(),
+            session_type: log.session_type.clone(),
+            logged_at,
+        })
+    }
+
+    async fn all_sessions(&self, user_id: i64) -> DbResult<Vec<Session>> {
+        timed(
+            "sqlite:all_sessions",
+            sqlx::query_as::<_, Session>("SELECT * FROM session WHERE user_id = ? ORDER BY date")
+                .bind(user_id)
+                .fetch_all(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn find_session(&self, session_id: i64) -> DbResult<Option<Session>> {
+        timed(
+            "sqlite:find_session",
+            sqlx::query_as::<_, Session>("SELECT * FROM session WHERE id = ?")
+                .bind(session_id)
+                .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn all_users(&self) -> DbResult<Vec<User>> {
+        timed(
+            "sqlite:all_users",
+            sqlx::query_as::<_, User>(
+                "SELECT id, token, tz_offset_minutes, username, password_hash FROM users",
+            )
+            .fetch_all(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn get_job_state(&self, job: &str) -> DbResult<Option<String>> {
+        timed(
+            "sqlite:get_job_state",
+            sqlx::query_scalar::<_, String>("SELECT value FROM job_state WHERE job = ?")
+                .bind(job)
+                .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn set_job_state(&self, job: &str, value: &str) -> DbResult<()> {
+        timed(
+            "sqlite:set_job_state",
+            sqlx::query(
+                r#"
+                INSERT INTO job_state (job, value) VALUES (?, ?)
+                ON CONFLICT(job) DO UPDATE SET value = excluded.value
+                "#,
+            )
+            .bind(job)
+            .bind(value)
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?;
+
+        Ok(())
+    }
+
+    async fn index_session_terms(&self, session_id: i64, terms: &[String]) -> DbResult<()> {
+        for term in terms {
+            timed(
+                "sqlite:index_session_terms",
+                sqlx::query("INSERT INTO search_index (term, session_id) VALUES (?, ?)")
+                    .bind(term)
+                    .bind(session_id)
+                    .execute(&self.pool),
+            )
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))?;
+        }
+
+        Ok(())
+    }
+
+    async fn session_ids_for_terms(&self, user_id: i64, terms: &[String]) -> DbResult<Vec<i64>> {
+        if terms.is_empty() {
+            return Ok(Vec::new());
+        }
+
+        let placeholders = terms.iter().map(|_| "?").collect::<Vec<_>>().join(", ");
+        let sql = format!(
+            r#"
+            SELECT si.session_id FROM search_index si
+            JOIN session s ON s.id = si.session_id
+            WHERE si.term IN ({}) AND s.user_id = ?
+            "#,
+            placeholders
+        );
+
+        let mut query = sqlx::query_scalar(&sql);
+        for term in terms {
+            query = query.bind(term);
+        }
+        query = query.bind(user_id);
+
+        timed("sqlite:session_ids_for_terms", query.fetch_all(&self.pool))
+            .await
+            .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn session_ids_for_prefix(&self, user_id: i64, prefix: &str) -> DbResult<Vec<i64>> {
+        let pattern = format!("{}%", prefix);
+        timed(
+            "sqlite:session_ids_for_prefix",
+            sqlx::query_scalar(
+                r#"
+                SELECT si.session_id FROM search_index si
+                JOIN session s ON s.id = si.session_id
+                WHERE si.term LIKE ? AND s.user_id = ?
+                "#,
+            )
+            .bind(pattern)
+            .bind(user_id)
+            .fetch_all(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+
+    async fn insert_attachment(
+        &self,
+        session_id: i64,
+        filename: &str,
+        content_type: &str,
+        data: &[u8],
+        uploaded_at: &str,
+    ) -> DbResult<Attachment> {
+        let id = timed(
+            "sqlite:insert_attachment",
+            sqlx::query(
+                r#"
+                INSERT INTO attachment (session_id, filename, content_type, byte_length, uploaded_at, data)
+                VALUES (?, ?, ?, ?, ?, ?)
+                "#,
+            )
+            .bind(session_id)
+            .bind(filename)
+            .bind(content_type)
+            .bind(data.len() as i64)
+            .bind(uploaded_at)
+            .bind(data)
+            .execute(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))?
+        .last_insert_rowid();
+
+        Ok(Attachment {
+            id,
+            session_id,
+            filename: filename.to_string(),
+            content_type: content_type.to_string(),
+            byte_length: data.len() as i64,
+            uploaded_at: uploaded_at.to_string(),
+            data: data.to_vec(),
+        })
+    }
+
+    async fn find_attachment(
+        &self,
+        session_id: i64,
+        attachment_id: i64,
+    ) -> DbResult<Option<Attachment>> {
+        timed(
+            "sqlite:find_attachment",
+            sqlx::query_as::<_, Attachment>(
+                "SELECT * FROM attachment WHERE id = ? AND session_id = ?",
+            )
+            .bind(attachment_id)
+            .bind(session_id)
+            .fetch_optional(&self.pool),
+        )
+        .await
+        .map_err(|e| DbError::Query(e.to_string()))
+    }
+}
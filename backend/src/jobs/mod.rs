@@ -0,0 +1,58 @@
+mod notifier;
+mod weekly_report;
+
+pub use notifier::{LogNotifier, Notifier, WeeklySummary};
+#[cfg(feature = "email-notifier")]
+pub use notifier::EmailNotifier;
+
+use std::sync::Arc;
+use std::time::Duration;
+
+use crate::db::Database;
+
+const DEFAULT_INTERVAL_SECS: u64 = 24 * 60 * 60;
+
+/// Configuration for the background job scheduler, sourced from the environment.
+pub struct JobConfig {
+    pub enabled: bool,
+    pub interval: Duration,
+}
+
+impl JobConfig {
+    /// Reads `WEEKLY_REPORT_ENABLED` (default: disabled) and
+    /// `WEEKLY_REPORT_INTERVAL_SECS` (default: once a day) from the environment.
+    pub fn from_env() -> Self {
+        let enabled = std::env::var("WEEKLY_REPORT_ENABLED")
+            .map(|value| value == "1" || value.eq_ignore_ascii_case("true"))
+            .unwrap_or(false);
+
+        let interval = std::env::var("WEEKLY_REPORT_INTERVAL_SECS")
+            .ok()
+            .and_then(|value| value.parse().ok())
+            .unwrap_or(DEFAULT_INTERVAL_SECS);
+
+        Self {
+            enabled,
+            interval: Duration::from_secs(interval),
+        }
+    }
+}
+
+/// Spawn the recurring weekly-summary task if `config.enabled`, dispatching
+/// through `notifier` once per `config.interval`.
+pub fn spawn(db: Arc<dyn Database>, notifier: Arc<dyn Notifier>, config: JobConfig) {
+    if !config.enabled {
+        tracing::info!("weekly report job disabled");
+        return;
+    }
+
+    tokio::spawn(async move {
+        let mut ticker = tokio::time::interval(config.interval);
+        loop {
+            ticker.tick().await;
+            if let Err(err) = weekly_report::run_once(db.as_ref(), notifier.as_ref()).await {
+                tracing::error!("weekly report job failed: {}", err);
+            }
+        }
+    });
+}
@@ -0,0 +1,51 @@
+use async_trait::async_trait;
+
+/// A single user's computed weekly summary, ready to hand to a `Notifier`.
+#[derive(Debug, Clone)]
+pub struct WeeklySummary {
+    pub user_id: i64,
+    pub points: usize,
+    pub streak: usize,
+    pub new_achievements: Vec<String>,
+}
+
+/// Delivers a computed `WeeklySummary` through some channel.
+#[async_trait]
+pub trait Notifier: Send + Sync {
+    async fn notify(&self, summary: &WeeklySummary);
+}
+
+/// Writes the summary to the tracing log — the default, dependency-free notifier.
+pub struct LogNotifier;
+
+#[async_trait]
+impl Notifier for LogNotifier {
+    async fn notify(&self, summary: &WeeklySummary) {
+        tracing::info!(
+            user_id = summary.user_id,
+            points = summary.points,
+            streak = summary.streak,
+            achievements = ?summary.new_achievements,
+            "weekly summary"
+        );
+    }
+}
+
+/// Sends the summary by email. Stubbed out behind the `email-notifier` feature
+/// until a real mail transport is wired in.
+#[cfg(feature = "email-notifier")]
+pub struct EmailNotifier {
+    pub smtp_url: String,
+}
+
+#[cfg(feature = "email-notifier")]
+#[async_trait]
+impl Notifier for EmailNotifier {
+    async fn notify(&self, summary: &WeeklySummary) {
+        tracing::info!(
+            user_id = summary.user_id,
+            smtp_url = %self.smtp_url,
+            "would send weekly summary email"
+        );
+    }
+}
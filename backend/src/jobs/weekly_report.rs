@@ -0,0 +1,74 @@
+use std::collections::HashSet;
+
+use chrono::{Duration, Utc};
+
+use super::{Notifier, WeeklySummary};
+use crate::db::Database;
+use crate::{calculate_session_points, calculate_statistics, parse_date};
+
+const JOB_NAME: &str = "weekly_report";
+
+/// Job-state key tracking which achievements `user_id` has already been notified about.
+fn earned_achievements_key(user_id: i64) -> String {
+    format!("{JOB_NAME}:achievements:{user_id}")
+}
+
+/// Compute and dispatch one weekly summary per user, skipping the run entirely
+/// if it has already happened today (so a restart never double-sends).
+pub async fn run_once(
+    db: &dyn Database,
+    notifier: &dyn Notifier,
+) -> Result<(), Box<dyn std::error::Error>> {
+    let today = Utc::now().date_naive();
+
+    if db.get_job_state(JOB_NAME).await? == Some(today.to_string()) {
+        return Ok(());
+    }
+
+    let week_ago = today - Duration::days(7);
+
+    for user in db.all_users().await? {
+        let sessions = db.all_sessions(user.id).await?;
+
+        let points: usize = sessions
+            .iter()
+            .filter(|s| {
+                parse_date(&s.date)
+                    .map(|date| date > week_ago && date <= today)
+                    .unwrap_or(false)
+            })
+            .map(|s| calculate_session_points(&s.session_type))
+            .sum();
+
+        let (_, earned, streak, _, _) =
+            calculate_statistics(&sessions, today).unwrap_or_else(|_| (Vec::new(), Vec::new(), 0, 0, 0));
+
+        let state_key = earned_achievements_key(user.id);
+        let previously_earned: HashSet<String> = db
+            .get_job_state(&state_key)
+            .await?
+            .map(|value| value.split(',').filter(|s| !s.is_empty()).map(str::to_string).collect())
+            .unwrap_or_default();
+
+        let new_achievements: Vec<String> = earned
+            .iter()
+            .filter(|name| !previously_earned.contains(*name))
+            .cloned()
+            .collect();
+
+        db.set_job_state(&state_key, &earned.join(",")).await?;
+
+        notifier
+            .notify(&WeeklySummary {
+                user_id: user.id,
+                points,
+                streak,
+                new_achievements,
+            })
+            .await;
+    }
+
+    db.set_job_state(JOB_NAME, &today.to_string()).await?;
+
+    Ok(())
+}
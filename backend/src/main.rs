@@ -1,18 +1,43 @@
+use actix_multipart::Multipart;
+use actix_session::storage::CookieSessionStore;
+use actix_session::SessionMiddleware;
+use actix_web::body::MessageBody;
+use actix_web::cookie::Key;
+use actix_web::dev::{ServiceRequest, ServiceResponse};
+use actix_web::middleware::{from_fn, Next};
 use actix_web::{web, App, HttpResponse, HttpServer, Responder, Result};
-use chrono::{Datelike, Duration, NaiveDate, Utc};
+use chrono::{Datelike, Duration, NaiveDate, NaiveDateTime, NaiveTime, Utc};
 use dotenv::dotenv;
+use futures::{try_join, TryStreamExt};
 use itertools::Itertools;
 use serde::{Deserialize, Serialize};
 use sqlx::FromRow;
 use std::collections::HashMap;
 use std::fmt::{Display, Formatter};
+use std::sync::Arc;
+use std::time::Instant;
+
+mod achievements;
+mod auth;
+mod compression;
+mod csrf;
+mod db;
+mod jobs;
+mod search;
+
+use auth::AuthUser;
+use compression::{CompressionConfig, ResponseCompression};
+use csrf::{CsrfConfig, CsrfProtection};
+use db::{Attachment, Database, SqliteDb, SqliteSettings};
 
 // Data models
 #[derive(Serialize, Deserialize, Clone, FromRow)]
 struct Session {
     id: i64,
+    user_id: i64,
     date: String,         // Format: "YYYY-MM-DD"
     session_type: String, // "1-hour", "2-hours", "3-hours"
+    logged_at: String,    // UTC timestamp, format: "YYYY-MM-DD HH:MM:SS"
 }
 
 #[derive(Serialize, Deserialize)]
@@ -32,14 +57,74 @@ struct ErrorResponse {
 struct SessionLog {
     date: String,         // Format: "YYYY-MM-DD"
     session_type: String, // "1-hour", "2-hours", "3-hours"
+    time: Option<String>, // Optional UTC time-of-day, format: "HH:MM:SS"; defaults to the user's local noon
 }
 
-#[derive(Serialize)]
+#[derive(Serialize, Clone)]
 struct WeeklyActivity {
     week_start: String, // Start date of the week
     points: usize,      // Total points for the week
 }
 
+/// Query-parameter filter applied to a user's sessions before any statistics are computed.
+#[derive(Deserialize)]
+struct SessionFilter {
+    from: Option<String>,
+    to: Option<String>,
+    session_type: Option<String>,
+    min_points: Option<usize>,
+}
+
+impl SessionFilter {
+    /// Reject an inverted date range up front, rather than silently returning an empty result.
+    fn validate(&self) -> Result<(), ApiError> {
+        if let (Some(from), Some(to)) = (&self.from, &self.to) {
+            if parse_date(from)? > parse_date(to)? {
+                return Err(ApiError::InvalidInput(
+                    "`from` must not be after `to`".into(),
+                ));
+            }
+        }
+        Ok(())
+    }
+
+    /// Keep only sessions within the inclusive date range, matching the exact
+    /// session type, and worth at least `min_points`.
+    fn apply(&self, sessions: Vec<Session>) -> Vec<Session> {
+        sessions
+            .into_iter()
+            .filter(|session| {
+                let Ok(date) = parse_date(&session.date) else {
+                    return false;
+                };
+
+                if let Some(from) = self.from.as_deref().and_then(|d| parse_date(d).ok()) {
+                    if date < from {
+                        return false;
+                    }
+                }
+                if let Some(to) = self.to.as_deref().and_then(|d| parse_date(d).ok()) {
+                    if date > to {
+                        return false;
+                    }
+                }
+                if let Some(session_type) = &self.session_type {
+                    if &session.session_type != session_type {
+                        return false;
+                    }
+                }
+                if let Some(min_points) = self.min_points {
+                    if calculate_session_points(&session.session_type) < min_points {
+                        return false;
+                    }
+                }
+
+                true
+            })
+            .collect()
+    }
+}
+
 // New structs for achievements and streaks
 #[derive(Serialize)]
 struct StreaksResponse {
@@ -56,7 +141,7 @@ struct StreakBonusResponse {
 
 #[derive(Serialize)]
 struct AchievementsResponse {
-    achievements: Vec<String>,
+    achievements: Vec<achievements::AchievementStatus>,
 }
 
 #[derive(Serialize)]
@@ -70,6 +155,21 @@ struct StatisticsResponse {
     monthly_streak: usize,
 }
 
+/// One day in the contribution-heatmap grid. `date` is `None` for the
+/// lead-in cells before the 365-day window starts.
+#[derive(Serialize)]
+struct HeatmapCell {
+    date: Option<String>,
+    total: usize,
+    level: u8,
+}
+
+#[derive(Serialize)]
+struct HeatmapResponse {
+    /// Columns of 7 day-cells each (Monday through Sunday), oldest week first.
+    weeks: Vec<Vec<HeatmapCell>>,
+}
+
 // Custom error type
 #[derive(Debug)]
 pub enum ApiError {
@@ -111,8 +211,9 @@ impl actix_web::error::ResponseError for ApiError {
 
 // Fetch the current time and calculate streaks
 pub async fn get_time(
-    pool: web::Data<sqlx::SqlitePool>,
+    db: web::Data<Arc<dyn Database>>,
     query: web::Query<HashMap<String, String>>,
+    user: AuthUser,
 ) -> Result<HttpResponse, actix_web::Error> {
     let current_time = if let Some(date_str) = query.get("date") {
         NaiveDate::parse_from_str(date_str, "%Y-%m-%d")
@@ -123,11 +224,17 @@ pub async fn get_time(
         Utc::now().naive_utc()
     };
 
-    // Fetch sessions to calculate streak and total points
-    let (sessions, _, total_points) = fetch_sessions(&pool)
+    let account = db
+        .find_user(user.user_id())
+        .await
+        .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?
+        .ok_or_else(|| actix_web::error::ErrorUnauthorized("Invalid API token"))?;
+
+    // Fetch sessions to calculate streak (in the user's own timezone) and total points
+    let (sessions, _, total_points) = fetch_sessions(db.get_ref().as_ref(), user.user_id())
         .await
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
-    let (streak, _) = calculate_streak_and_points(&sessions)
+    let (streak, _) = calculate_streak_and_points_tz(&sessions, account.tz_offset_minutes)
         .map_err(|e| actix_web::error::ErrorInternalServerError(e.to_string()))?;
 
     Ok(HttpResponse::Ok().json(TimeResponse {
@@ -139,8 +246,9 @@ pub async fn get_time(
 }
 
 async fn create_session(
-    pool: web::Data<sqlx::SqlitePool>,
+    db: web::Data<Arc<dyn Database>>,
     session_log: web::Json<SessionLog>,
+    user: AuthUser,
 ) -> Result<impl Responder, ApiError> {
     let date = parse_date(&session_log.date)?;
     let valid_session_types = ["1-hour", "2-hours", "3-hours"];
@@ -148,16 +256,38 @@ async fn create_session(
         return Err(ApiError::InvalidInput("Invalid session type".into()));
     }
 
-    // Insert session into the database
-    sqlx::query("INSERT INTO session (date, session_type) VALUES (?, ?)")
-        .bind(&session_log.date)
-        .bind(&session_log.session_type)
-        .execute(pool.get_ref())
-        .await
-        .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    let account = db
+        .find_user(user.user_id())
+        .await?
+        .ok_or_else(|| ApiError::InvalidInput("Unknown user".into()))?;
 
-    let (sessions, _, total_points) = fetch_sessions(pool.get_ref()).await?;
-    let (streak, _) = calculate_streak_and_points(&sessions)?;
+    let logged_at = match &session_log.time {
+        Some(time_str) => {
+            let time = NaiveTime::parse_from_str(time_str, "%H:%M:%S")
+                .map_err(|_| ApiError::InvalidInput("Invalid time format".into()))?;
+            date.and_time(time)
+        }
+        // No time given: anchor on local noon rather than UTC midnight, so the
+        // tz-localized streak date always lands on the `date` the caller logged,
+        // regardless of which way their tz_offset_minutes points.
+        None => {
+            let local_noon = date.and_hms_opt(12, 0, 0).unwrap();
+            local_noon - Duration::minutes(account.tz_offset_minutes as i64)
+        }
+    };
+
+    let session = db
+        .insert_session(user.user_id(), &session_log, logged_at)
+        .await?;
+
+    let terms: Vec<String> = search::tokenize(&session.date)
+        .into_iter()
+        .chain(search::tokenize(&session.session_type))
+        .collect();
+    db.index_session_terms(session.id, &terms).await?;
+
+    let (sessions, _, total_points) = fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+    let (streak, _) = calculate_streak_and_points_tz(&sessions, account.tz_offset_minutes)?;
 
     Ok(HttpResponse::Created().json(TimeResponse {
         current_time: session_log.date.clone(),
@@ -167,15 +297,196 @@ async fn create_session(
     }))
 }
 
-// Fetch sessions stored in the database
+#[derive(Deserialize)]
+struct SearchQuery {
+    q: String,
+}
+
+#[derive(Serialize)]
+struct SearchResponse {
+    query: String,
+    results: Vec<Session>,
+}
+
+/// `GET /api/sessions/search?q=...` — typo-tolerant full-text search over the caller's
+/// own logged sessions, ranked by number of matched query terms. Query terms longer
+/// than 3 characters also match edit-distance-1 typos; the last term additionally
+/// matches as a prefix, so an in-progress word still returns results.
+async fn search_sessions(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+    query: web::Query<SearchQuery>,
+) -> Result<impl Responder, ApiError> {
+    let query_terms = search::tokenize(&query.q);
+    let last_index = query_terms.len().checked_sub(1);
+
+    let mut matches_per_term = Vec::with_capacity(query_terms.len());
+    for (i, term) in query_terms.iter().enumerate() {
+        let candidates = search::candidate_terms(term);
+        let mut session_ids: std::collections::HashSet<i64> = db
+            .session_ids_for_terms(user.user_id(), &candidates)
+            .await?
+            .into_iter()
+            .collect();
+        if Some(i) == last_index {
+            session_ids.extend(db.session_ids_for_prefix(user.user_id(), term).await?);
+        }
+
+        matches_per_term.push(session_ids);
+    }
+
+    let ranked_ids = search::rank_by_matched_terms(matches_per_term);
+
+    let (sessions, _, _) = fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+    let sessions_by_id: HashMap<i64, Session> =
+        sessions.into_iter().map(|session| (session.id, session)).collect();
+    let results = ranked_ids
+        .into_iter()
+        .filter_map(|id| sessions_by_id.get(&id).cloned())
+        .collect();
+
+    Ok(HttpResponse::Ok().json(SearchResponse {
+        query: query.q.clone(),
+        results,
+    }))
+}
+
+/// Per-file cap on a proof attachment upload.
+const MAX_ATTACHMENT_BYTES: usize = 10 * 1024 * 1024;
+
+const ALLOWED_ATTACHMENT_CONTENT_TYPES: [&str; 5] = [
+    "image/png",
+    "image/jpeg",
+    "image/gif",
+    "text/plain",
+    "application/json",
+];
+
+#[derive(Serialize)]
+struct AttachmentResponse {
+    id: i64,
+    session_id: i64,
+    filename: String,
+    content_type: String,
+    byte_length: i64,
+    uploaded_at: String,
+}
+
+impl From<Attachment> for AttachmentResponse {
+    fn from(attachment: Attachment) -> Self {
+        Self {
+            id: attachment.id,
+            session_id: attachment.session_id,
+            filename: attachment.filename,
+            content_type: attachment.content_type,
+            byte_length: attachment.byte_length,
+            uploaded_at: attachment.uploaded_at,
+        }
+    }
+}
+
+/// Confirm `session_id` exists and is owned by `user_id`, or fail with the same
+/// "Unknown session" message either way so callers can't probe for other users' ids.
+async fn authorize_session(
+    db: &dyn Database,
+    session_id: i64,
+    user_id: i64,
+) -> Result<Session, ApiError> {
+    let session = db
+        .find_session(session_id)
+        .await?
+        .ok_or_else(|| ApiError::InvalidInput("Unknown session".into()))?;
+    if session.user_id != user_id {
+        return Err(ApiError::InvalidInput("Unknown session".into()));
+    }
+    Ok(session)
+}
+
+/// `POST /api/sessions/{id}/attachments` — upload a proof attachment (photo, screenshot,
+/// exported log) for a session the caller owns, as a single-file multipart body.
+async fn upload_attachment(
+    db: web::Data<Arc<dyn Database>>,
+    path: web::Path<i64>,
+    user: AuthUser,
+    mut payload: Multipart,
+) -> Result<impl Responder, ApiError> {
+    let session_id = path.into_inner();
+    authorize_session(db.get_ref().as_ref(), session_id, user.user_id()).await?;
+
+    let mut field = payload
+        .try_next()
+        .await
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))?
+        .ok_or_else(|| ApiError::InvalidInput("Missing attachment file".into()))?;
+
+    let filename = field
+        .content_disposition()
+        .and_then(|disposition| disposition.get_filename())
+        .unwrap_or("attachment")
+        .to_string();
+    let content_type = field
+        .content_type()
+        .map(|mime| mime.to_string())
+        .unwrap_or_else(|| "application/octet-stream".to_string());
+
+    if !ALLOWED_ATTACHMENT_CONTENT_TYPES.contains(&content_type.as_str()) {
+        return Err(ApiError::InvalidInput(format!(
+            "Unsupported content type '{}'",
+            content_type
+        )));
+    }
+
+    let mut data = Vec::new();
+    while let Some(chunk) = field
+        .try_next()
+        .await
+        .map_err(|e| ApiError::InvalidInput(e.to_string()))?
+    {
+        if data.len() + chunk.len() > MAX_ATTACHMENT_BYTES {
+            return Err(ApiError::InvalidInput(format!(
+                "Attachment exceeds the {MAX_ATTACHMENT_BYTES}-byte limit"
+            )));
+        }
+        data.extend_from_slice(&chunk);
+    }
+
+    let uploaded_at = Utc::now()
+        .naive_utc()
+        .format("%Y-%m-%d %H:%M:%S")
+        .to_string();
+    let attachment = db
+        .insert_attachment(session_id, &filename, &content_type, &data, &uploaded_at)
+        .await?;
+
+    Ok(HttpResponse::Created().json(AttachmentResponse::from(attachment)))
+}
+
+/// `GET /api/sessions/{id}/attachments/{attachment_id}` — stream an attachment's raw
+/// bytes back with its stored `Content-Type`.
+async fn download_attachment(
+    db: web::Data<Arc<dyn Database>>,
+    path: web::Path<(i64, i64)>,
+    user: AuthUser,
+) -> Result<impl Responder, ApiError> {
+    let (session_id, attachment_id) = path.into_inner();
+    authorize_session(db.get_ref().as_ref(), session_id, user.user_id()).await?;
+
+    let attachment = db
+        .find_attachment(session_id, attachment_id)
+        .await?
+        .ok_or_else(|| ApiError::InvalidInput("Unknown attachment".into()))?;
+
+    Ok(HttpResponse::Ok()
+        .content_type(attachment.content_type.clone())
+        .body(attachment.data))
+}
+
+// Fetch sessions stored in the database, scoped to a single user
 async fn fetch_sessions(
-    pool: &sqlx::SqlitePool,
+    db: &dyn Database,
+    user_id: i64,
 ) -> Result<(Vec<Session>, String, usize), ApiError> {
-    let sessions: Vec<Session> =
-        sqlx::query_as::<_, Session>("SELECT * FROM session ORDER BY date")
-            .fetch_all(pool)
-            .await
-            .map_err(|e| ApiError::DatabaseError(e.to_string()))?;
+    let sessions: Vec<Session> = db.all_sessions(user_id).await?;
 
     let current_date = if let Some(last_session) = sessions.last() {
         last_session.date.clone()
@@ -235,6 +546,72 @@ fn calculate_streak_and_points(sessions: &[Session]) -> Result<(usize, usize), A
         .map(|(streak, total_points, _)| (streak, total_points)) // Ignore last_date in the result
 }
 
+/// Like `calculate_streak_and_points`, but tracks the longest run seen anywhere in
+/// `sessions` rather than just the trailing one — so a window with a gap (e.g. the
+/// 1st-3rd then the 20th) reports `3`, not the `1`-session run still open at the end.
+fn calculate_max_streak_and_points(sessions: &[Session]) -> Result<(usize, usize), ApiError> {
+    let fold_fn = |(streak, max_streak, total_points, last_date): (
+        usize,
+        usize,
+        usize,
+        Option<NaiveDate>,
+    ),
+                   session: &Session|
+     -> Result<(usize, usize, usize, Option<NaiveDate>), ApiError> {
+        let current_date = parse_date(&session.date)?;
+        let session_points = calculate_session_points(&session.session_type);
+        let new_streak = update_streak(last_date, current_date, streak);
+
+        Ok((
+            new_streak,
+            max_streak.max(new_streak),
+            total_points + session_points,
+            Some(current_date),
+        ))
+    };
+
+    sessions
+        .iter()
+        .sorted_by(|a, b| a.date.cmp(&b.date))
+        .try_fold((0, 0, 0, None), fold_fn)
+        .map(|(_, max_streak, total_points, _)| (max_streak, total_points))
+}
+
+/// Like `calculate_streak_and_points`, but groups sessions by the calendar date they
+/// fall on in the user's own timezone rather than the raw (UTC-entered) `date` field —
+/// so an 11pm session and the next morning's don't get miscounted as non-consecutive
+/// (or vice versa) just because they straddle UTC midnight.
+fn calculate_streak_and_points_tz(
+    sessions: &[Session],
+    tz_offset_minutes: i32,
+) -> Result<(usize, usize), ApiError> {
+    let offset = Duration::minutes(tz_offset_minutes as i64);
+
+    let mut local_sessions: Vec<(NaiveDate, &Session)> = sessions
+        .iter()
+        .map(|session| {
+            let logged_at = NaiveDateTime::parse_from_str(&session.logged_at, "%Y-%m-%d %H:%M:%S")
+                .map_err(|_| ApiError::InvalidInput("Invalid logged_at timestamp".into()))?;
+            Ok(((logged_at + offset).date(), session))
+        })
+        .collect::<Result<_, ApiError>>()?;
+    local_sessions.sort_by_key(|(local_date, _)| *local_date);
+
+    let fold_fn = |(streak, total_points, last_date): (usize, usize, Option<NaiveDate>),
+                   (local_date, session): &(NaiveDate, &Session)|
+     -> Result<(usize, usize, Option<NaiveDate>), ApiError> {
+        let session_points = calculate_session_points(&session.session_type);
+        let new_streak = update_streak(last_date, *local_date, streak);
+
+        Ok((new_streak, total_points + session_points, Some(*local_date)))
+    };
+
+    local_sessions
+        .iter()
+        .try_fold((0, 0, None), fold_fn)
+        .map(|(streak, total_points, _)| (streak, total_points))
+}
+
 fn get_week_start(date: NaiveDate) -> NaiveDate {
     date - Duration::days(date.weekday().num_days_from_monday() as i64)
 }
@@ -266,6 +643,143 @@ fn calculate_trend(sessions: &[Session], period: &str) -> Vec<WeeklyActivity> {
         .collect()
 }
 
+/// The canonical label for `date` under `interval` — the same labels `calculate_trend` uses.
+fn interval_label(date: NaiveDate, interval: &str) -> String {
+    match interval {
+        "day" => date.to_string(),
+        "week" => get_week_start(date).to_string(),
+        "month" => format!("{}-{:02}", date.year(), date.month()),
+        "year" => date.year().to_string(),
+        _ => date.to_string(),
+    }
+}
+
+/// The start of the interval-sized bucket containing `date`.
+fn interval_start(date: NaiveDate, interval: &str) -> NaiveDate {
+    match interval {
+        "week" => get_week_start(date),
+        "month" => NaiveDate::from_ymd_opt(date.year(), date.month(), 1).unwrap(),
+        "year" => NaiveDate::from_ymd_opt(date.year(), 1, 1).unwrap(),
+        _ => date,
+    }
+}
+
+/// Step a cursor forward by one `interval`.
+fn advance_interval(date: NaiveDate, interval: &str) -> NaiveDate {
+    match interval {
+        "week" => date + Duration::days(7),
+        "month" => {
+            if date.month() == 12 {
+                NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).unwrap()
+            } else {
+                NaiveDate::from_ymd_opt(date.year(), date.month() + 1, 1).unwrap()
+            }
+        }
+        "year" => NaiveDate::from_ymd_opt(date.year() + 1, 1, 1).unwrap(),
+        _ => date + Duration::days(1),
+    }
+}
+
+/// Build a contiguous series of `WeeklyActivity` points, one per `interval` step
+/// across `[from, to]`, filling gaps with `points: 0` instead of dropping them.
+fn calculate_timeseries(
+    sessions: &[Session],
+    interval: &str,
+    from: NaiveDate,
+    to: NaiveDate,
+) -> Vec<WeeklyActivity> {
+    let mut points_by_label: HashMap<String, usize> = HashMap::new();
+    for session in sessions {
+        if let Ok(date) = parse_date(&session.date) {
+            let label = interval_label(date, interval);
+            *points_by_label.entry(label).or_insert(0) +=
+                calculate_session_points(&session.session_type);
+        }
+    }
+
+    let mut series = Vec::new();
+    let mut cursor = interval_start(from, interval);
+    let end = interval_start(to, interval);
+
+    while cursor <= end {
+        let label = interval_label(cursor, interval);
+        let points = *points_by_label.get(&label).unwrap_or(&0);
+        series.push(WeeklyActivity {
+            week_start: label,
+            points,
+        });
+        cursor = advance_interval(cursor, interval);
+    }
+
+    series
+}
+
+/// Build a GitHub-style contribution heatmap over the trailing 365 days (ending yesterday).
+fn calculate_heatmap(sessions: &[Session]) -> HeatmapResponse {
+    let end = Utc::now().date_naive() - Duration::days(1);
+    let start = end - Duration::days(365);
+
+    let mut totals: HashMap<NaiveDate, usize> = HashMap::new();
+    for session in sessions {
+        if let Ok(date) = parse_date(&session.date) {
+            if date >= start && date <= end {
+                *totals.entry(date).or_insert(0) += calculate_session_points(&session.session_type);
+            }
+        }
+    }
+
+    let mut nonzero: Vec<usize> = totals.values().copied().filter(|&total| total > 0).collect();
+    nonzero.sort_unstable();
+
+    let quantile = |p: f64| -> usize {
+        if nonzero.is_empty() {
+            0
+        } else {
+            nonzero[(((nonzero.len() - 1) as f64) * p).round() as usize]
+        }
+    };
+    let (q1, q2, q3) = (quantile(0.25), quantile(0.5), quantile(0.75));
+
+    let level_for = |total: usize| -> u8 {
+        match total {
+            0 => 0,
+            t if t <= q1 => 1,
+            t if t <= q2 => 2,
+            t if t <= q3 => 3,
+            _ => 4,
+        }
+    };
+
+    // Align the first column on the Monday on/before `start` so every week is a full 7 days.
+    let grid_start = start - Duration::days(start.weekday().num_days_from_monday() as i64);
+
+    let mut weeks = Vec::new();
+    let mut cursor = grid_start;
+    while cursor <= end {
+        let mut column = Vec::with_capacity(7);
+        for _ in 0..7 {
+            if cursor < start {
+                column.push(HeatmapCell {
+                    date: None,
+                    total: 0,
+                    level: 0,
+                });
+            } else {
+                let total = *totals.get(&cursor).unwrap_or(&0);
+                column.push(HeatmapCell {
+                    date: Some(cursor.to_string()),
+                    total,
+                    level: level_for(total),
+                });
+            }
+            cursor += Duration::days(1);
+        }
+        weeks.push(column);
+    }
+
+    HeatmapResponse { weeks }
+}
+
 fn calculate_period_streak<F>(sessions: &[Session], filter_fn: F) -> usize
 where
     F: Fn(NaiveDate) -> bool,
@@ -291,14 +805,7 @@ fn calculate_statistics(
     let weekly_trend: Vec<WeeklyActivity> = calculate_trend(sessions, "week");
 
     // Calculate overall streak directly
-    let (overall_streak, _total_points) = calculate_streak_and_points(sessions)?;
-
-    // Determine achievements based on the overall streak
-    let achievements = if overall_streak >= 7 {
-        vec!["7-day streak".to_string()]
-    } else {
-        Vec::new()
-    };
+    let (overall_streak, total_points) = calculate_streak_and_points(sessions)?;
 
     // Calculate yearly streak
     let current_year = current_date.year();
@@ -308,6 +815,15 @@ fn calculate_statistics(
     let current_month = current_date.month();
     let monthly_streak = calculate_period_streak(sessions, |date| date.month() == current_month);
 
+    let achievements = achievements::earned_names(&achievements::StatsContext {
+        overall_streak,
+        yearly_streak,
+        monthly_streak,
+        total_points,
+        total_sessions: sessions.len(),
+        weekly_trend: weekly_trend.clone(),
+    });
+
     Ok((
         weekly_trend,
         achievements,
@@ -318,24 +834,115 @@ fn calculate_statistics(
 }
 
 // Additional endpoint implementations
-async fn get_weekly_trend(pool: web::Data<sqlx::SqlitePool>) -> Result<impl Responder, ApiError> {
-    let (sessions, _current_date, _total_points) = fetch_sessions(&pool).await?;
+async fn get_weekly_trend(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+    filter: web::Query<SessionFilter>,
+) -> Result<impl Responder, ApiError> {
+    filter.validate()?;
+
+    let (sessions, _current_date, _total_points) =
+        fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+    let sessions = filter.apply(sessions);
     let (weekly_trend, _, _, _, _) = calculate_statistics(&sessions, Default::default())?;
 
     Ok(HttpResponse::Ok().json(weekly_trend))
 }
 
-async fn get_achievements(pool: web::Data<sqlx::SqlitePool>) -> Result<impl Responder, ApiError> {
-    let (sessions, _current_date, _total_points) = fetch_sessions(&pool).await?;
-    let (_, achievements, _, _, _) = calculate_statistics(&sessions, Default::default())?;
+const VALID_INTERVALS: [&str; 4] = ["day", "week", "month", "year"];
+
+async fn get_timeseries(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+    query: web::Query<HashMap<String, String>>,
+) -> Result<impl Responder, ApiError> {
+    let interval = query.get("interval").map(String::as_str).unwrap_or("week");
+    if !VALID_INTERVALS.contains(&interval) {
+        return Err(ApiError::InvalidInput(format!(
+            "Invalid interval '{}', expected one of {:?}",
+            interval, VALID_INTERVALS
+        )));
+    }
+
+    let (sessions, _current_date, _total_points) =
+        fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+
+    let session_dates: Vec<NaiveDate> = sessions
+        .iter()
+        .filter_map(|s| parse_date(&s.date).ok())
+        .collect();
+
+    let from = match query.get("from") {
+        Some(date_str) => parse_date(date_str)?,
+        None => session_dates
+            .iter()
+            .min()
+            .copied()
+            .unwrap_or_else(|| Utc::now().date_naive()),
+    };
+    let to = match query.get("to") {
+        Some(date_str) => parse_date(date_str)?,
+        None => session_dates
+            .iter()
+            .max()
+            .copied()
+            .unwrap_or_else(|| Utc::now().date_naive()),
+    };
+
+    if from > to {
+        return Err(ApiError::InvalidInput(
+            "`from` must not be after `to`".into(),
+        ));
+    }
+
+    Ok(HttpResponse::Ok().json(calculate_timeseries(&sessions, interval, from, to)))
+}
+
+async fn get_achievements(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+    filter: web::Query<SessionFilter>,
+) -> Result<impl Responder, ApiError> {
+    filter.validate()?;
+
+    let (sessions, current_date, _total_points) =
+        fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+    let sessions = filter.apply(sessions);
+    let current_date = parse_date(&current_date).unwrap_or_else(|_| Utc::now().date_naive());
+    let total_points: usize = sessions
+        .iter()
+        .map(|s| calculate_session_points(&s.session_type))
+        .sum();
+    let (weekly_trend, _, overall_streak, yearly_streak, monthly_streak) =
+        calculate_statistics(&sessions, current_date)?;
 
-    Ok(HttpResponse::Ok().json(AchievementsResponse { achievements }))
+    let catalog = achievements::evaluate_all(&achievements::StatsContext {
+        overall_streak,
+        yearly_streak,
+        monthly_streak,
+        total_points,
+        total_sessions: sessions.len(),
+        weekly_trend,
+    });
+
+    Ok(HttpResponse::Ok().json(AchievementsResponse {
+        achievements: catalog,
+    }))
 }
 
-async fn get_streaks(pool: web::Data<sqlx::SqlitePool>) -> Result<impl Responder, ApiError> {
-    let (sessions, _current_date, _total_points) = fetch_sessions(&pool).await?;
+async fn get_streaks(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+    filter: web::Query<SessionFilter>,
+) -> Result<impl Responder, ApiError> {
+    filter.validate()?;
+
+    let (sessions, current_date, _total_points) =
+        fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+    let sessions = filter.apply(sessions);
+    let current_date = parse_date(&current_date).unwrap_or_else(|_| Utc::now().date_naive());
     let (_, _, overall_streak, yearly_streak, monthly_streak) =
-        calculate_statistics(&sessions, Default::default())?;
+        calculate_statistics(&sessions, current_date)?;
 
     Ok(HttpResponse::Ok().json(StreaksResponse {
         overall_streak,
@@ -344,13 +951,53 @@ async fn get_streaks(pool: web::Data<sqlx::SqlitePool>) -> Result<impl Responder
     }))
 }
 
-async fn get_streak_bonuses(pool: web::Data<sqlx::SqlitePool>) -> Result<impl Responder, ApiError> {
-    let (sessions, _current_date, _total_points) = fetch_sessions(&pool).await?;
+async fn get_streak_bonuses(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+) -> Result<impl Responder, ApiError> {
+    let (sessions, _current_date, _total_points) =
+        fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
     let bonuses = calculate_weekly_streak_bonus(&sessions);
 
     Ok(HttpResponse::Ok().json(bonuses))
 }
 
+/// Combined response for the main dashboard view, replacing separate round-trips
+/// to `/api/time` and `/api/bonuses/streaks`.
+#[derive(Serialize)]
+struct DashboardResponse {
+    streak: usize,
+    total_points: usize,
+    streak_bonuses: Vec<StreakBonusResponse>,
+}
+
+/// `GET /api/dashboard` — fans the user lookup and session fetch out concurrently
+/// with `try_join!`, then derives streak/points/bonuses from the one session fetch.
+async fn get_dashboard(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+) -> Result<impl Responder, ApiError> {
+    let db_ref = db.get_ref().as_ref();
+    let (account, sessions) = try_join!(
+        db_ref.find_user(user.user_id()),
+        db_ref.all_sessions(user.user_id())
+    )?;
+    let account = account.ok_or_else(|| ApiError::InvalidInput("Unknown user".into()))?;
+
+    let total_points: usize = sessions
+        .iter()
+        .map(|s| calculate_session_points(&s.session_type))
+        .sum();
+    let (streak, _) = calculate_streak_and_points_tz(&sessions, account.tz_offset_minutes)?;
+    let streak_bonuses = calculate_weekly_streak_bonus(&sessions);
+
+    Ok(HttpResponse::Ok().json(DashboardResponse {
+        streak,
+        total_points,
+        streak_bonuses,
+    }))
+}
+
 fn calculate_weekly_streak_bonus(sessions: &[Session]) -> Vec<StreakBonusResponse> {
     let (finalized_streaks, last_date, last_streak) = sessions
         .iter()
@@ -405,11 +1052,22 @@ fn calculate_weekly_streak_bonus(sessions: &[Session]) -> Vec<StreakBonusRespons
 }
 
 async fn get_overall_statistics(
-    pool: web::Data<sqlx::SqlitePool>,
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+    filter: web::Query<SessionFilter>,
 ) -> Result<impl Responder, ApiError> {
-    let (sessions, current_date, total_points) = fetch_sessions(&pool).await?;
+    filter.validate()?;
+
+    let (sessions, current_date, _total_points) =
+        fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+    let sessions = filter.apply(sessions);
+    let total_points: usize = sessions
+        .iter()
+        .map(|s| calculate_session_points(&s.session_type))
+        .sum();
+    let parsed_current_date = parse_date(&current_date).unwrap_or_else(|_| Utc::now().date_naive());
     let (weekly_trend, achievements, overall_streak, yearly_streak, monthly_streak) =
-        calculate_statistics(&sessions, Default::default())?;
+        calculate_statistics(&sessions, parsed_current_date)?;
 
     Ok(HttpResponse::Ok().json(StatisticsResponse {
         current_date,
@@ -422,39 +1080,209 @@ async fn get_overall_statistics(
     }))
 }
 
+const VALID_STATS_PERIODS: [&str; 5] = ["day", "week", "month", "year", "all"];
+
+/// Aggregated totals for a rolling window, e.g. "points this week".
+#[derive(Serialize)]
+struct StatsResponse {
+    period: String,
+    sessions: usize,
+    total_points: usize,
+    best_streak: usize,
+}
+
+/// `GET /api/stats` — all-time aggregation, equivalent to `/api/stats/all`.
+async fn get_stats(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+) -> Result<impl Responder, ApiError> {
+    calculate_stats_response(db.get_ref().as_ref(), user.user_id(), "all").await
+}
+
+/// `GET /api/stats/{period}` — aggregation over `day`, `week`, `month`, `year`, or `all`.
+async fn get_stats_for_period(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+    period: web::Path<String>,
+) -> Result<impl Responder, ApiError> {
+    calculate_stats_response(db.get_ref().as_ref(), user.user_id(), &period).await
+}
+
+async fn calculate_stats_response(
+    db: &dyn Database,
+    user_id: i64,
+    period: &str,
+) -> Result<HttpResponse, ApiError> {
+    if !VALID_STATS_PERIODS.contains(&period) {
+        return Err(ApiError::InvalidInput(format!(
+            "Invalid period '{}', expected one of {:?}",
+            period, VALID_STATS_PERIODS
+        )));
+    }
+
+    let (sessions, _current_date, _total_points) = fetch_sessions(db, user_id).await?;
+    let today = Utc::now().date_naive();
+    let windowed: Vec<Session> = sessions
+        .into_iter()
+        .filter(|session| {
+            parse_date(&session.date)
+                .map(|date| session_in_period(date, today, period))
+                .unwrap_or(false)
+        })
+        .collect();
+
+    let total_points: usize = windowed
+        .iter()
+        .map(|s| calculate_session_points(&s.session_type))
+        .sum();
+    let (best_streak, _) = calculate_max_streak_and_points(&windowed)?;
+
+    Ok(HttpResponse::Ok().json(StatsResponse {
+        period: period.to_string(),
+        sessions: windowed.len(),
+        total_points,
+        best_streak,
+    }))
+}
+
+fn session_in_period(date: NaiveDate, today: NaiveDate, period: &str) -> bool {
+    match period {
+        "day" => date == today,
+        "week" => get_week_start(date) == get_week_start(today),
+        "month" => date.year() == today.year() && date.month() == today.month(),
+        "year" => date.year() == today.year(),
+        _ => true, // "all"
+    }
+}
+
+async fn get_heatmap(
+    db: web::Data<Arc<dyn Database>>,
+    user: AuthUser,
+) -> Result<impl Responder, ApiError> {
+    let (sessions, _current_date, _total_points) =
+        fetch_sessions(db.get_ref().as_ref(), user.user_id()).await?;
+
+    Ok(HttpResponse::Ok().json(calculate_heatmap(&sessions)))
+}
+
+/// Pick a `Database` backend from `DATABASE_BACKEND` (`sqlite`, the default, or
+/// `postgres`). Postgres reads its connection string from `DATABASE_URL`; SQLite
+/// always uses the in-memory `SqliteSettings::default()` and ignores `DATABASE_URL`.
+async fn connect_db() -> db::DbResult<Arc<dyn Database>> {
+    let backend = std::env::var("DATABASE_BACKEND").unwrap_or_else(|_| "sqlite".to_string());
+
+    match backend.as_str() {
+        #[cfg(feature = "postgres")]
+        "postgres" => {
+            let database_url = std::env::var("DATABASE_URL").map_err(|_| {
+                db::DbError::Connection("DATABASE_URL is required for the postgres backend".into())
+            })?;
+            tracing::info!(database_url, "using Postgres database");
+            let db = db::PostgresDb::connect(db::PostgresSettings { database_url }).await?;
+            Ok(Arc::new(db))
+        }
+        _ => {
+            let settings = SqliteSettings::default();
+            tracing::info!(database_url = settings.database_url, "using SQLite database");
+            let db = SqliteDb::connect(settings).await?;
+            Ok(Arc::new(db))
+        }
+    }
+}
+
+/// Signing key for cookie sessions, sourced from `SESSION_SECRET` (must be at least
+/// 64 bytes) so sessions survive a restart, falling back to a fresh random key.
+fn session_key() -> Key {
+    match std::env::var("SESSION_SECRET") {
+        Ok(secret) if secret.len() >= 64 => Key::from(secret.as_bytes()),
+        _ => Key::generate(),
+    }
+}
+
+/// Log the route, status, and elapsed time of every request, so operators can spot
+/// slow endpoints alongside the per-query timing recorded in the DB layer.
+async fn trace_requests(
+    req: ServiceRequest,
+    next: Next<impl MessageBody>,
+) -> std::result::Result<ServiceResponse<impl MessageBody>, actix_web::Error> {
+    let method = req.method().clone();
+    let path = req.path().to_string();
+    let start = Instant::now();
+
+    let result = next.call(req).await;
+    let elapsed_ms = start.elapsed().as_millis();
+
+    match &result {
+        Ok(res) => tracing::info!(
+            %method,
+            %path,
+            status = res.status().as_u16(),
+            elapsed_ms,
+            "request handled"
+        ),
+        Err(err) => tracing::warn!(%method, %path, elapsed_ms, error = %err, "request failed"),
+    }
+
+    result
+}
+
 #[actix_web::main]
 async fn main() -> std::io::Result<()> {
     dotenv().ok(); // Ensure this line is present
 
-    let database_url = "sqlite::memory:";
+    tracing_subscriber::fmt()
+        .with_env_filter(tracing_subscriber::EnvFilter::from_default_env())
+        .init();
 
-    // Print the database URL for debugging purposes
-    println!("Using database URL: {}", database_url);
-
-    let pool = sqlx::sqlite::SqlitePoolOptions::new()
-        .max_connections(5)
-        .connect(&database_url)
-        .await
-        .expect("Failed to create pool.");
+    let db = connect_db().await.expect("Failed to create pool.");
 
     // Initialize the database
-    init_db(&pool)
-        .await
-        .expect("Failed to initialize database.");
+    db.init().await.expect("Failed to initialize database.");
+
+    // Spawn the weekly-summary background job alongside the HTTP server.
+    jobs::spawn(
+        db.clone(),
+        Arc::new(jobs::LogNotifier),
+        jobs::JobConfig::from_env(),
+    );
 
-    // Share the pool across routes
-    let pool = web::Data::new(pool);
+    // Share the database across routes
+    let db = web::Data::new(db);
+    let session_key = session_key();
 
     HttpServer::new(move || {
         App::new()
-            .app_data(pool.clone())
+            .app_data(db.clone())
+            .wrap(SessionMiddleware::new(
+                CookieSessionStore::default(),
+                session_key.clone(),
+            ))
+            .wrap(CsrfProtection::new(CsrfConfig::default()))
+            .wrap(from_fn(trace_requests))
+            .wrap(ResponseCompression::new(CompressionConfig::default()))
             .route("/", web::get().to(api_docs)) // Add the documentation endpoint
+            .route("/api/register", web::post().to(auth::register))
+            .route("/api/login", web::post().to(auth::login))
             .route("/api/time", web::get().to(get_time))
             .route("/api/log_session", web::post().to(create_session))
+            .route("/api/sessions/search", web::get().to(search_sessions))
+            .route(
+                "/api/sessions/{id}/attachments",
+                web::post().to(upload_attachment),
+            )
+            .route(
+                "/api/sessions/{id}/attachments/{attachment_id}",
+                web::get().to(download_attachment),
+            )
             .route(
                 "/api/statistics/weekly_trend",
                 web::get().to(get_weekly_trend),
             )
+            .route(
+                "/api/statistics/timeseries",
+                web::get().to(get_timeseries),
+            )
+            .route("/api/heatmap", web::get().to(get_heatmap))
             .route(
                 "/api/statistics/achievements",
                 web::get().to(get_achievements),
@@ -465,28 +1293,15 @@ async fn main() -> std::io::Result<()> {
                 web::get().to(get_overall_statistics),
             )
             .route("/api/bonuses/streaks", web::get().to(get_streak_bonuses))
+            .route("/api/stats", web::get().to(get_stats))
+            .route("/api/stats/{period}", web::get().to(get_stats_for_period))
+            .route("/api/dashboard", web::get().to(get_dashboard))
     })
         .bind("127.0.0.1:8080")?
         .run()
         .await
 }
 
-async fn init_db(pool: &sqlx::SqlitePool) -> Result<(), sqlx::Error> {
-    sqlx::query(
-        r#"
-        CREATE TABLE IF NOT EXISTS session (
-            id INTEGER PRIMARY KEY AUTOINCREMENT,
-            date TEXT NOT NULL,
-            session_type TEXT NOT NULL
-        );
-        "#,
-    )
-        .execute(pool)
-        .await?;
-
-    Ok(())
-}
-
 /// Generate an API documentation page
 async fn api_docs() -> impl Responder {
     let doc_content = r#"
@@ -525,10 +1340,31 @@ async fn api_docs() -> impl Responder {
                     <tr>
                         <td>/api/log_session</td>
                         <td>POST</td>
-                        <td>Create a new session log entry.</td>
-                        <td><pre>{ "date": "2023-10-01", "session_type": "1-hour" }</pre></td>
+                        <td>Create a new session log entry. `time` is optional (UTC, defaults to noon in the user's timezone).</td>
+                        <td><pre>{ "date": "2023-10-01", "session_type": "1-hour", "time": "23:15:00" }</pre></td>
                         <td><pre>{ "current_time": "2023-10-01T00:00:00Z", "streak": 3, "total_points": 36, "date": "2023-10-01" }</pre></td>
                     </tr>
+                    <tr>
+                        <td>/api/sessions/search</td>
+                        <td>GET</td>
+                        <td>Typo-tolerant full-text search over the caller's own sessions, ranked by matched terms.</td>
+                        <td><pre>?q=1-hour oct</pre></td>
+                        <td><pre>{ "query": "1-hour oct", "results": [{ "id": 1, "user_id": 1, "date": "2023-10-01", "session_type": "1-hour", "logged_at": "2023-10-01 00:00:00" }] }</pre></td>
+                    </tr>
+                    <tr>
+                        <td>/api/sessions/{id}/attachments</td>
+                        <td>POST</td>
+                        <td>Upload a proof attachment (multipart, single file) for a session you own.</td>
+                        <td><pre>multipart/form-data; file field, e.g. "proof.png"</pre></td>
+                        <td><pre>{ "id": 1, "session_id": 1, "filename": "proof.png", "content_type": "image/png", "byte_length": 2048, "uploaded_at": "2023-10-01 00:00:00" }</pre></td>
+                    </tr>
+                    <tr>
+                        <td>/api/sessions/{id}/attachments/{attachment_id}</td>
+                        <td>GET</td>
+                        <td>Stream an attachment's bytes back with its stored content type.</td>
+                        <td>N/A</td>
+                        <td>Raw file bytes</td>
+                    </tr>
                     <tr>
                         <td>/api/statistics/weekly_trend</td>
                         <td>GET</td>
@@ -536,12 +1372,26 @@ async fn api_docs() -> impl Responder {
                         <td>N/A</td>
                         <td><pre>[{ "week_start": "2023-09-25", "points": 36 }]</pre></td>
                     </tr>
+                    <tr>
+                        <td>/api/statistics/timeseries</td>
+                        <td>GET</td>
+                        <td>Contiguous, zero-filled activity series for a date range and interval.</td>
+                        <td><pre>?interval=month&amp;from=2023-09-01&amp;to=2023-11-30</pre></td>
+                        <td><pre>[{ "week_start": "2023-09", "points": 0 }, { "week_start": "2023-10", "points": 36 }, { "week_start": "2023-11", "points": 0 }]</pre></td>
+                    </tr>
+                    <tr>
+                        <td>/api/heatmap</td>
+                        <td>GET</td>
+                        <td>GitHub-style contribution calendar for the trailing 365 days.</td>
+                        <td>N/A</td>
+                        <td><pre>{ "weeks": [[{ "date": null, "total": 0, "level": 0 }, ...]] }</pre></td>
+                    </tr>
                     <tr>
                         <td>/api/statistics/achievements</td>
                         <td>GET</td>
-                        <td>Fetch user achievements.</td>
+                        <td>Fetch the full achievement catalog with earned status and progress.</td>
                         <td>N/A</td>
-                        <td><pre>{ "achievements": [] }</pre></td>
+                        <td><pre>{ "achievements": [{ "id": "streak-7", "name": "7-day streak", "earned": false, "progress": 0.43 }] }</pre></td>
                     </tr>
                     <tr>
                         <td>/api/statistics/streaks</td>
@@ -564,6 +1414,20 @@ async fn api_docs() -> impl Responder {
                         <td>N/A</td>
                         <td><pre>[{ "streak_length": 3, "week_start": "2023-09-25" }]</pre></td>
                     </tr>
+                    <tr>
+                        <td>/api/stats/{period}</td>
+                        <td>GET</td>
+                        <td>Aggregated totals over `day`, `week`, `month`, `year`, or `all`. `/api/stats` defaults to `all`.</td>
+                        <td>N/A</td>
+                        <td><pre>{ "period": "week", "sessions": 4, "total_points": 46, "best_streak": 3 }</pre></td>
+                    </tr>
+                    <tr>
+                        <td>/api/dashboard</td>
+                        <td>GET</td>
+                        <td>Streak, total points, and streak bonuses in one call, fetched concurrently.</td>
+                        <td>N/A</td>
+                        <td><pre>{ "streak": 3, "total_points": 36, "streak_bonuses": [{ "streak_length": 3, "week_start": "2023-09-25" }] }</pre></td>
+                    </tr>
                 </tbody>
             </table>
         </body>
@@ -586,18 +1450,24 @@ mod tests {
         vec![
             Session {
                 id: 1,
+                user_id: 1,
                 date: "2023-10-01".to_string(),
                 session_type: "1-hour".to_string(),
+                logged_at: "2023-10-01 00:00:00".to_string(),
             },
             Session {
                 id: 2,
+                user_id: 1,
                 date: "2023-10-02".to_string(),
                 session_type: "2-hours".to_string(),
+                logged_at: "2023-10-02 00:00:00".to_string(),
             },
             Session {
                 id: 3,
+                user_id: 1,
                 date: "2023-10-03".to_string(),
                 session_type: "3-hours".to_string(),
+                logged_at: "2023-10-03 00:00:00".to_string(),
             },
         ]
     }
@@ -680,42 +1550,41 @@ mod tests {
         #[actix_rt::test]
         async fn test_get_time_endpoint() {
             // Set up an in-memory SQLite database for testing
-            let pool = sqlx::sqlite::SqlitePoolOptions::new()
-                .connect(":memory:")
-                .await
-                .unwrap();
+            let db: Arc<dyn Database> = Arc::new(SqliteDb::connect(SqliteSettings::default()).await.unwrap());
+            db.init().await.unwrap();
 
-            init_db(&pool).await.unwrap();
+            let user = db.create_user("test-token", 0).await.unwrap();
 
             // Insert sample sessions into the test database
             let sample_sessions = vec![
                 SessionLog {
                     date: "2023-10-01".to_string(),
                     session_type: "1-hour".to_string(),
+                    time: None,
                 },
                 SessionLog {
                     date: "2023-10-02".to_string(),
                     session_type: "2-hours".to_string(),
+                    time: None,
                 },
             ];
 
             for session in sample_sessions {
-                sqlx::query("INSERT INTO session (date, session_type) VALUES (?, ?)")
-                    .bind(&session.date)
-                    .bind(&session.session_type)
-                    .execute(&pool)
-                    .await
-                    .unwrap();
+                let logged_at = parse_date(&session.date).unwrap().and_hms_opt(0, 0, 0).unwrap();
+                db.insert_session(user.id, &session, logged_at).await.unwrap();
             }
 
             let app = test::init_service(
                 App::new()
-                    .app_data(web::Data::new(pool.clone()))
+                    .app_data(web::Data::new(db.clone()))
                     .route("/api/time", web::get().to(get_time)),
             )
                 .await;
 
-            let req = test::TestRequest::get().uri("/api/time").to_request();
+            let req = test::TestRequest::get()
+                .uri("/api/time")
+                .insert_header(("Authorization", format!("Bearer {}", user.token)))
+                .to_request();
             let resp: TimeResponse = test::call_and_read_body_json(&app, req).await;
 
             // Assert to confirm streak calculation works correctly
@@ -725,33 +1594,38 @@ mod tests {
 
         #[actix_rt::test]
         async fn test_get_streak_bonuses_endpoint() {
-            let pool = sqlx::sqlite::SqlitePoolOptions::new()
-                .connect(":memory:")
-                .await
-                .unwrap();
+            let db: Arc<dyn Database> = Arc::new(SqliteDb::connect(SqliteSettings::default()).await.unwrap());
+            db.init().await.unwrap();
 
-            init_db(&pool).await.unwrap();
+            let user = db.create_user("test-token", 0).await.unwrap();
 
             // Add sample data to the database
             let sample_data = sample_sessions();
             for session in sample_data {
-                sqlx::query("INSERT INTO session (date, session_type) VALUES (?, ?)")
-                    .bind(&session.date)
-                    .bind(&session.session_type)
-                    .execute(&pool)
-                    .await
-                    .unwrap();
+                let logged_at = parse_date(&session.date).unwrap().and_hms_opt(0, 0, 0).unwrap();
+                db.insert_session(
+                    user.id,
+                    &SessionLog {
+                        date: session.date.clone(),
+                        session_type: session.session_type.clone(),
+                        time: None,
+                    },
+                    logged_at,
+                )
+                .await
+                .unwrap();
             }
 
             let app = test::init_service(
                 App::new()
-                    .app_data(web::Data::new(pool.clone()))
+                    .app_data(web::Data::new(db.clone()))
                     .route("/api/bonuses/streaks", web::get().to(get_streak_bonuses)),
             )
                 .await;
 
             let req = test::TestRequest::get()
                 .uri("/api/bonuses/streaks")
+                .insert_header(("Authorization", format!("Bearer {}", user.token)))
                 .to_request();
             let resp: Vec<StreakBonusResponse> = test::call_and_read_body_json(&app, req).await;
 
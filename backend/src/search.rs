@@ -0,0 +1,81 @@
+use std::collections::{HashMap, HashSet};
+
+/// Lowercase `text` and split it into alphanumeric terms, discarding everything else.
+/// Used both to build the search index on write and to tokenize a query at read time.
+pub fn tokenize(text: &str) -> Vec<String> {
+    text.to_lowercase()
+        .split(|c: char| !c.is_alphanumeric())
+        .filter(|term| !term.is_empty())
+        .map(str::to_string)
+        .collect()
+}
+
+const FUZZY_ALPHABET: &str = "abcdefghijklmnopqrstuvwxyz0123456789";
+
+/// All edit-distance-1 variants of `term` (single insertion, deletion, substitution,
+/// or adjacent transposition) over `[a-z0-9]`, for typo-tolerant matching.
+pub fn edit_distance_1_variants(term: &str) -> HashSet<String> {
+    let chars: Vec<char> = term.chars().collect();
+    let mut variants = HashSet::new();
+
+    // Deletions
+    for i in 0..chars.len() {
+        let mut variant = chars.clone();
+        variant.remove(i);
+        variants.insert(variant.into_iter().collect());
+    }
+
+    // Transpositions of adjacent characters
+    for i in 0..chars.len().saturating_sub(1) {
+        let mut variant = chars.clone();
+        variant.swap(i, i + 1);
+        variants.insert(variant.into_iter().collect());
+    }
+
+    // Substitutions and insertions
+    for i in 0..=chars.len() {
+        for letter in FUZZY_ALPHABET.chars() {
+            if i < chars.len() {
+                let mut variant = chars.clone();
+                variant[i] = letter;
+                variants.insert(variant.into_iter().collect());
+            }
+
+            let mut variant = chars.clone();
+            variant.insert(i, letter);
+            variants.insert(variant.into_iter().collect());
+        }
+    }
+
+    variants.remove(term);
+    variants
+}
+
+/// Terms longer than this get fuzzy (edit-distance-1) matching in addition to exact.
+const FUZZY_MIN_LEN: usize = 3;
+
+/// One query term resolved to its candidate index terms: itself, plus edit-distance-1
+/// variants when it's long enough to make those meaningfully distinct.
+pub fn candidate_terms(term: &str) -> Vec<String> {
+    let mut candidates = vec![term.to_string()];
+    if term.len() > FUZZY_MIN_LEN {
+        candidates.extend(edit_distance_1_variants(term));
+    }
+    candidates
+}
+
+/// Rank session ids by how many distinct query terms matched, descending, with ties
+/// broken by ascending session id. `matches_per_term` is one entry per query term, each holding every
+/// session id that term (or one of its fuzzy/prefix variants) matched.
+pub fn rank_by_matched_terms(matches_per_term: Vec<HashSet<i64>>) -> Vec<i64> {
+    let mut matched_term_count: HashMap<i64, usize> = HashMap::new();
+    for matches in &matches_per_term {
+        for &session_id in matches {
+            *matched_term_count.entry(session_id).or_insert(0) += 1;
+        }
+    }
+
+    let mut ranked: Vec<(i64, usize)> = matched_term_count.into_iter().collect();
+    ranked.sort_by(|a, b| b.1.cmp(&a.1).then(a.0.cmp(&b.0)));
+    ranked.into_iter().map(|(session_id, _)| session_id).collect()
+}